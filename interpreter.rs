@@ -15,11 +15,23 @@ enum ArithmeticOperator {
   MINUS,
   TIMES,
   DIV,
+  MOD,
+  POW,
+  BITAND,
+  BITOR,
+  BITXOR,
+  SHL,
+  SHR,
   NONE,
 }
 #[derive(Copy, Clone)]
 enum ComparisonOperator {
   EQ,
+  NEQ,
+  LT,
+  LE,
+  GT,
+  GE,
   NONE,
 }
 
@@ -31,6 +43,9 @@ enum ComparisonOperator {
 enum ExpressionTag {
   INT_CONST,
   BOOL_CONST,
+  STRING_CONST,
+  ARRAY_LIT,
+  INDEX,
   BIN_OP,
   COMP,
   IF,
@@ -45,6 +60,7 @@ struct Expression {
   body: Vec<Expression>, // The operands or the rest of the program
   intConst: i32, // For integer constants
   boolConst: bool, // For boolean constants
+  stringConst: String, // For string constants
   op: ArithmeticOperator, // For arithmetic operations
   comp: ComparisonOperator, // For comparisons
   name: String, // For variable reads and writes
@@ -58,6 +74,7 @@ impl Expression {
         body: Vec::new(),
         intConst: value,
         boolConst: false,
+        stringConst: String::new(),
         op: ArithmeticOperator::NONE,
         comp: ComparisonOperator::NONE,
         name: String::new(),
@@ -70,6 +87,50 @@ impl Expression {
         body: Vec::new(),
         intConst: 0,
         boolConst: value,
+        stringConst: String::new(),
+        op: ArithmeticOperator::NONE,
+        comp: ComparisonOperator::NONE,
+        name: String::new(),
+        argNames: Vec::new(),
+      }
+    }
+    fn stringConstant(value: String) -> Expression {
+      Expression {
+        tag: ExpressionTag::STRING_CONST,
+        body: Vec::new(),
+        intConst: 0,
+        boolConst: false,
+        stringConst: value,
+        op: ArithmeticOperator::NONE,
+        comp: ComparisonOperator::NONE,
+        name: String::new(),
+        argNames: Vec::new(),
+      }
+    }
+    fn arrayLiteral(elements: Vec<Expression>) -> Expression {
+      Expression {
+        tag: ExpressionTag::ARRAY_LIT,
+        body: elements,
+        intConst: 0,
+        boolConst: false,
+        stringConst: String::new(),
+        op: ArithmeticOperator::NONE,
+        comp: ComparisonOperator::NONE,
+        name: String::new(),
+        argNames: Vec::new(),
+      }
+    }
+    fn index(collection: Expression, indexExpr: Expression) -> Expression {
+      let mut operands : Vec<Expression> = Vec::new();
+      operands.push(collection);
+      operands.push(indexExpr);
+
+      Expression {
+        tag: ExpressionTag::INDEX,
+        body: operands,
+        intConst: 0,
+        boolConst: false,
+        stringConst: String::new(),
         op: ArithmeticOperator::NONE,
         comp: ComparisonOperator::NONE,
         name: String::new(),
@@ -86,6 +147,7 @@ impl Expression {
         body: operands,
         intConst: 0,
         boolConst: false,
+        stringConst: String::new(),
         op: operator,
         comp: ComparisonOperator::NONE,
         name: String::new(),
@@ -102,6 +164,7 @@ impl Expression {
         body: operands,
         intConst: 0,
         boolConst: false,
+        stringConst: String::new(),
         op: ArithmeticOperator::NONE,
         comp: comparison,
         name: String::new(),
@@ -119,6 +182,7 @@ impl Expression {
         body: operands,
         intConst: 0,
         boolConst: false,
+        stringConst: String::new(),
         op: ArithmeticOperator::NONE,
         comp: ComparisonOperator::NONE,
         name: String::new(),
@@ -135,6 +199,7 @@ impl Expression {
         body: operands,
         intConst: 0,
         boolConst: false,
+        stringConst: String::new(),
         op: ArithmeticOperator::NONE,
         comp: ComparisonOperator::NONE,
         name: varName,
@@ -147,6 +212,7 @@ impl Expression {
         body: Vec::new(),
         intConst: 0,
         boolConst: false,
+        stringConst: String::new(),
         op: ArithmeticOperator::NONE,
         comp: ComparisonOperator::NONE,
         name: varName,
@@ -162,6 +228,7 @@ impl Expression {
         body: body,
         intConst: 0,
         boolConst: false,
+        stringConst: String::new(),
         op: ArithmeticOperator::NONE,
         comp: ComparisonOperator::NONE,
         name: String::new(),
@@ -180,6 +247,7 @@ impl Expression {
         body: body,
         intConst: 0,
         boolConst: false,
+        stringConst: String::new(),
         op: ArithmeticOperator::NONE,
         comp: ComparisonOperator::NONE,
         name: name,
@@ -191,7 +259,8 @@ impl Expression {
       match self.tag {
         ExpressionTag::INT_CONST => Value::integer(self.intConst),
         ExpressionTag::BOOL_CONST => Value::boolean(self.boolConst),
-        ExpressionTag::FUNC_CALL => Value::function(Expression::clone(&self.body[0]), Vec::clone(&self.argNames)),
+        ExpressionTag::STRING_CONST => Value::string(self.stringConst.clone()),
+        ExpressionTag::FUNC_CALL => Value::function(Expression::clone(&self.body[0]), Vec::clone(&self.argNames), Environment::new()),
         _ => Value::null(),
       }
     }
@@ -202,22 +271,36 @@ impl std::fmt::Display for Expression {
     match self.tag {
       ExpressionTag::INT_CONST => write!(f, "INT_CONST:{}", self.intConst),
       ExpressionTag::BOOL_CONST => write!(f, "BOOL_CONST:{}", self.boolConst),
+      ExpressionTag::STRING_CONST => write!(f, "STRING_CONST:\"{}\"", self.stringConst),
+      ExpressionTag::ARRAY_LIT => write!(f, "ARRAY_LIT"),
+      ExpressionTag::INDEX => write!(f, "INDEX"),
       ExpressionTag::BIN_OP => {
         let op = self.op;
         match op {
-          ArithmeticOperator::PLUS  => write!(f, "BIN_OP:PLUS"),
-          ArithmeticOperator::MINUS => write!(f, "BIN_OP:MINUS"),
-          ArithmeticOperator::TIMES => write!(f, "BIN_OP:TIMES"),
-          ArithmeticOperator::DIV   => write!(f, "BIN_OP:DIV"),
-          ArithmeticOperator::NONE  => write!(f, "BIN_OP:NONE"),
+          ArithmeticOperator::PLUS   => write!(f, "BIN_OP:PLUS"),
+          ArithmeticOperator::MINUS  => write!(f, "BIN_OP:MINUS"),
+          ArithmeticOperator::TIMES  => write!(f, "BIN_OP:TIMES"),
+          ArithmeticOperator::DIV    => write!(f, "BIN_OP:DIV"),
+          ArithmeticOperator::MOD    => write!(f, "BIN_OP:MOD"),
+          ArithmeticOperator::POW    => write!(f, "BIN_OP:POW"),
+          ArithmeticOperator::BITAND => write!(f, "BIN_OP:BITAND"),
+          ArithmeticOperator::BITOR  => write!(f, "BIN_OP:BITOR"),
+          ArithmeticOperator::BITXOR => write!(f, "BIN_OP:BITXOR"),
+          ArithmeticOperator::SHL    => write!(f, "BIN_OP:SHL"),
+          ArithmeticOperator::SHR    => write!(f, "BIN_OP:SHR"),
+          ArithmeticOperator::NONE   => write!(f, "BIN_OP:NONE"),
         }
       },
       ExpressionTag::COMP => {
         let comp = self.comp;
         match comp {
           ComparisonOperator::EQ   => write!(f, "COMP:EQ"),
+          ComparisonOperator::NEQ  => write!(f, "COMP:NEQ"),
+          ComparisonOperator::LT   => write!(f, "COMP:LT"),
+          ComparisonOperator::LE   => write!(f, "COMP:LE"),
+          ComparisonOperator::GT   => write!(f, "COMP:GT"),
+          ComparisonOperator::GE   => write!(f, "COMP:GE"),
           ComparisonOperator::NONE => write!(f, "COMP:NONE"),
-          //_   => write!(f, "COMP:unknown"),
         }
       },
       ExpressionTag::IF => write!(f, "IF"),
@@ -238,6 +321,8 @@ impl std::fmt::Display for Expression {
 enum ValueTag {
   INT,
   BOOL,
+  STRING,
+  ARRAY,
   FUNC,
   NULL
 }
@@ -246,8 +331,11 @@ struct Value {
   tag: ValueTag, // Value type
   intVal: i32, // For integer values
   boolVal: bool, // For boolean values
+  stringVal: String, // For string values
+  arrayVal: Vec<Value>, // For array values
   funcVal: Expression, // For function values
   formalFuncArgNames: Vec<String>, // For function values
+  capturedEnv: Environment, // The environment a function closes over, for function values
 }
 impl Value {
   // Constructors
@@ -256,8 +344,11 @@ impl Value {
         tag: ValueTag::INT,
         intVal: value,
         boolVal: false,
+        stringVal: String::new(),
+        arrayVal: Vec::new(),
         funcVal: Expression::booleanConstant(false),
         formalFuncArgNames: Vec::new(),
+        capturedEnv: Environment::new(),
       }
   }
   fn boolean(value: bool) -> Value {
@@ -265,8 +356,35 @@ impl Value {
         tag: ValueTag::BOOL,
         intVal: 0,
         boolVal: value,
+        stringVal: String::new(),
+        arrayVal: Vec::new(),
+        funcVal: Expression::booleanConstant(false),
+        formalFuncArgNames: Vec::new(),
+        capturedEnv: Environment::new(),
+      }
+  }
+  fn string(value: String) -> Value {
+      Value {
+        tag: ValueTag::STRING,
+        intVal: 0,
+        boolVal: false,
+        stringVal: value,
+        arrayVal: Vec::new(),
         funcVal: Expression::booleanConstant(false),
-        formalFuncArgNames: Vec::new()
+        formalFuncArgNames: Vec::new(),
+        capturedEnv: Environment::new(),
+      }
+  }
+  fn array(value: Vec<Value>) -> Value {
+      Value {
+        tag: ValueTag::ARRAY,
+        intVal: 0,
+        boolVal: false,
+        stringVal: String::new(),
+        arrayVal: value,
+        funcVal: Expression::booleanConstant(false),
+        formalFuncArgNames: Vec::new(),
+        capturedEnv: Environment::new(),
       }
   }
   fn null() -> Value {
@@ -274,17 +392,23 @@ impl Value {
         tag: ValueTag::NULL,
         intVal: 0,
         boolVal: false,
+        stringVal: String::new(),
+        arrayVal: Vec::new(),
         funcVal: Expression::booleanConstant(false),
-        formalFuncArgNames: Vec::new()
+        formalFuncArgNames: Vec::new(),
+        capturedEnv: Environment::new(),
       }
   }
-  fn function(body: Expression, argNames: Vec<String>) -> Value {
+  fn function(body: Expression, argNames: Vec<String>, capturedEnv: Environment) -> Value {
     Value {
         tag: ValueTag::FUNC,
         intVal: 0,
         boolVal: false,
+        stringVal: String::new(),
+        arrayVal: Vec::new(),
         funcVal: body,
         formalFuncArgNames: argNames,
+        capturedEnv: capturedEnv,
       }
   }
   fn isNull(&self) -> bool {
@@ -297,6 +421,11 @@ impl std::fmt::Display for Value {
     match self.tag {
       ValueTag::INT  => write!(f, "INT:{}", self.intVal),
       ValueTag::BOOL => write!(f, "BOOL:{}", self.boolVal),
+      ValueTag::STRING => write!(f, "STRING:\"{}\"", self.stringVal),
+      ValueTag::ARRAY => {
+        let elements : Vec<String> = self.arrayVal.iter().map(|v| v.to_string()).collect();
+        write!(f, "ARRAY:[{}]", elements.join(", "))
+      },
       ValueTag::FUNC => write!(f, "FUNC"),
       ValueTag::NULL => write!(f, "NULL"),
     }
@@ -384,10 +513,71 @@ impl Environment {
   }
 }
 
+//========================//
+// Runtime Error Definition //
+//========================//
+#[derive(Clone, Debug)]
+enum RuntimeErrorKind {
+  DivByZero,
+  TypeMismatch,
+  UnboundVariable,
+  NotAFunction,
+  ArityMismatch,
+  IndexOutOfBounds,
+}
+#[derive(Clone, Debug)]
+struct RuntimeError {
+  pc: usize,
+  message: String,
+  kind: RuntimeErrorKind,
+}
+impl RuntimeError {
+  fn new(pc: usize, kind: RuntimeErrorKind, message: String) -> RuntimeError {
+    RuntimeError {
+      pc: pc,
+      message: message,
+      kind: kind,
+    }
+  }
+}
+// Print Function
+impl std::fmt::Display for RuntimeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let kindStr = match self.kind {
+      RuntimeErrorKind::DivByZero => "DivByZero",
+      RuntimeErrorKind::TypeMismatch => "TypeMismatch",
+      RuntimeErrorKind::UnboundVariable => "UnboundVariable",
+      RuntimeErrorKind::NotAFunction => "NotAFunction",
+      RuntimeErrorKind::ArityMismatch => "ArityMismatch",
+      RuntimeErrorKind::IndexOutOfBounds => "IndexOutOfBounds",
+    };
+    write!(f, "RuntimeError[{}] at PC={}: {}", kindStr, self.pc, self.message)
+  }
+}
+
 //=====================//
 // Evaluate Definition //
 //=====================//
-fn evaluate(program : &Expression, mut pc : usize, e : &Environment) -> (Value, usize) {
+// Structural equality over Value, recursing into ARRAY elements. FUNC
+// values are never equal, matching the existing COMP semantics.
+fn valuesEqual(left: &Value, right: &Value) -> bool {
+  if left.tag != right.tag {
+    return false;
+  }
+  match left.tag {
+    ValueTag::INT => left.intVal == right.intVal,
+    ValueTag::BOOL => left.boolVal == right.boolVal,
+    ValueTag::STRING => left.stringVal == right.stringVal,
+    ValueTag::ARRAY => {
+      left.arrayVal.len() == right.arrayVal.len()
+        && left.arrayVal.iter().zip(right.arrayVal.iter()).all(|(l, r)| valuesEqual(l, r))
+    },
+    ValueTag::NULL => true,
+    ValueTag::FUNC => false,
+  }
+}
+
+fn evaluate(program : &Expression, mut pc : usize, e : &Environment) -> Result<(Value, usize), RuntimeError> {
   let exp : &Expression = &program;
   print!("PC={} -> {}\n", pc, exp);
   pc = pc + 1;
@@ -395,107 +585,1153 @@ fn evaluate(program : &Expression, mut pc : usize, e : &Environment) -> (Value,
   match exp.tag {
     ExpressionTag::INT_CONST => {
       let intVal : Value = exp.getValue();
-      (intVal, pc)
+      Ok((intVal, pc))
     },
     ExpressionTag::BOOL_CONST => {
       let boolVal : Value = exp.getValue();
-      (boolVal, pc)
+      Ok((boolVal, pc))
+    },
+    ExpressionTag::STRING_CONST => {
+      let stringVal : Value = exp.getValue();
+      Ok((stringVal, pc))
+    },
+    ExpressionTag::ARRAY_LIT => {
+      let mut elements : Vec<Value> = Vec::new();
+      let mut pc = pc;
+      for elementExpr in &exp.body {
+        let (elementVal, newPc) = evaluate(elementExpr, pc, e)?;
+        pc = newPc;
+        elements.push(elementVal);
+      }
+      Ok((Value::array(elements), pc))
+    },
+    ExpressionTag::INDEX => {
+      let (collectionVal, pc) = evaluate(&exp.body[0], pc, e)?;
+      let (indexVal, pc) = evaluate(&exp.body[1], pc, e)?;
+
+      if indexVal.tag != ValueTag::INT {
+        return Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, format!("index must be INT, got {}", indexVal)));
+      }
+      let index = indexVal.intVal;
+
+      match collectionVal.tag {
+        ValueTag::ARRAY => {
+          if index < 0 || (index as usize) >= collectionVal.arrayVal.len() {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::IndexOutOfBounds, format!("array index {} out of bounds (len {})", index, collectionVal.arrayVal.len())));
+          }
+          Ok((Value::clone(&collectionVal.arrayVal[index as usize]), pc))
+        },
+        ValueTag::STRING => {
+          let chars : Vec<char> = collectionVal.stringVal.chars().collect();
+          if index < 0 || (index as usize) >= chars.len() {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::IndexOutOfBounds, format!("string index {} out of bounds (len {})", index, chars.len())));
+          }
+          Ok((Value::string(chars[index as usize].to_string()), pc))
+        },
+        _ => Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, format!("cannot index into {}", collectionVal))),
+      }
     },
     ExpressionTag::BIN_OP => {
       let op : ArithmeticOperator =  exp.op;
-      let (leftVal, pc) = evaluate(&exp.body[0], pc, e);
-      let (rightVal, pc) = evaluate(&exp.body[1], pc, e);
+      let (leftVal, pc) = evaluate(&exp.body[0], pc, e)?;
+      let (rightVal, pc) = evaluate(&exp.body[1], pc, e)?;
+
+      if let ArithmeticOperator::PLUS = op {
+        match (leftVal.tag, rightVal.tag) {
+          (ValueTag::STRING, ValueTag::STRING) => {
+            return Ok((Value::string(format!("{}{}", leftVal.stringVal, rightVal.stringVal)), pc));
+          },
+          (ValueTag::ARRAY, ValueTag::ARRAY) => {
+            let mut combined = leftVal.arrayVal.clone();
+            combined.extend(rightVal.arrayVal.clone());
+            return Ok((Value::array(combined), pc));
+          },
+          _ => {},
+        }
+      }
+
+      if leftVal.tag != ValueTag::INT || rightVal.tag != ValueTag::INT {
+        return Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, format!("arithmetic operands must both be INT, got {} and {}", leftVal, rightVal)));
+      }
 
       let left = leftVal.intVal;
       let right = rightVal.intVal;
 
-      let res : i32;
       match op {
-        ArithmeticOperator::PLUS  => (Value::integer(left+right), pc),
-        ArithmeticOperator::MINUS => (Value::integer(left-right), pc),
-        ArithmeticOperator::TIMES => (Value::integer(left*right), pc),
-        ArithmeticOperator::DIV   => (Value::integer(left/right), pc),
-        ArithmeticOperator::NONE  => (Value::null(), pc)
+        ArithmeticOperator::PLUS  => Ok((Value::integer(left+right), pc)),
+        ArithmeticOperator::MINUS => Ok((Value::integer(left-right), pc)),
+        ArithmeticOperator::TIMES => Ok((Value::integer(left*right), pc)),
+        ArithmeticOperator::DIV   => {
+          if right == 0 {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::DivByZero, "division by zero".to_string()));
+          }
+          Ok((Value::integer(left/right), pc))
+        },
+        ArithmeticOperator::MOD => {
+          if right == 0 {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::DivByZero, "modulo by zero".to_string()));
+          }
+          Ok((Value::integer(left % right), pc))
+        },
+        ArithmeticOperator::POW => {
+          if right < 0 {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, "POW exponent must be non-negative".to_string()));
+          }
+          Ok((Value::integer(left.pow(right as u32)), pc))
+        },
+        ArithmeticOperator::BITAND => Ok((Value::integer(left & right), pc)),
+        ArithmeticOperator::BITOR  => Ok((Value::integer(left | right), pc)),
+        ArithmeticOperator::BITXOR => Ok((Value::integer(left ^ right), pc)),
+        ArithmeticOperator::SHL => {
+          if right < 0 || right >= 32 {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, "SHL amount must be in 0..32".to_string()));
+          }
+          Ok((Value::integer(left << right), pc))
+        },
+        ArithmeticOperator::SHR => {
+          if right < 0 || right >= 32 {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, "SHR amount must be in 0..32".to_string()));
+          }
+          Ok((Value::integer(left >> right), pc))
+        },
+        ArithmeticOperator::NONE  => Ok((Value::null(), pc)),
       }
     },
     ExpressionTag::COMP => {
       let comp : ComparisonOperator =  exp.comp;
-      let (leftVal, pc) = evaluate(&exp.body[0], pc, e);
-      let (rightVal, pc) = evaluate(&exp.body[1], pc, e);
- 
-      // Comparison between different types returns false
-      if leftVal.tag != rightVal.tag {
-        (Value::boolean(false), pc)
-      }
-      else {
-        match leftVal.tag {
-          ValueTag::INT => {
-            let left : i32 = leftVal.intVal;
-            let right : i32 = rightVal.intVal;   
-            (Value::boolean(left==right), pc)
-          }
-          ValueTag::BOOL => {
-            let left : bool = leftVal.boolVal;
-            let right : bool = rightVal.boolVal;   
-            (Value::boolean(left==right), pc)
-          }
-          ValueTag::NULL => {
-            // NULL==NULL evaluates to true
-            (Value::boolean(true), pc)
-          }
-          ValueTag::FUNC => {
-            (Value::boolean(false), pc)
+      let (leftVal, pc) = evaluate(&exp.body[0], pc, e)?;
+      let (rightVal, pc) = evaluate(&exp.body[1], pc, e)?;
+
+      match comp {
+        ComparisonOperator::EQ => Ok((Value::boolean(valuesEqual(&leftVal, &rightVal)), pc)),
+        ComparisonOperator::NEQ => Ok((Value::boolean(!valuesEqual(&leftVal, &rightVal)), pc)),
+        ComparisonOperator::LT | ComparisonOperator::LE | ComparisonOperator::GT | ComparisonOperator::GE => {
+          if leftVal.tag != ValueTag::INT || rightVal.tag != ValueTag::INT {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, format!("ordering comparisons require INT operands, got {} and {}", leftVal, rightVal)));
           }
-        }
+          let left = leftVal.intVal;
+          let right = rightVal.intVal;
+          let result = match comp {
+            ComparisonOperator::LT => left < right,
+            ComparisonOperator::LE => left <= right,
+            ComparisonOperator::GT => left > right,
+            ComparisonOperator::GE => left >= right,
+            _ => unreachable!(),
+          };
+          Ok((Value::boolean(result), pc))
+        },
+        ComparisonOperator::NONE => Ok((Value::boolean(false), pc)),
       }
     },
     ExpressionTag::IF => {
-      let (condVal, pc) = evaluate(&exp.body[0], pc, e);
+      let (condVal, pc) = evaluate(&exp.body[0], pc, e)?;
       if condVal.boolVal==true {
-        let (leftVal, pc) = evaluate(&exp.body[1], pc, e);
-        (leftVal, pc)
+        let (leftVal, pc) = evaluate(&exp.body[1], pc, e)?;
+        Ok((leftVal, pc))
       }
       else {
-        let (rightVal, pc) = evaluate(&exp.body[2], pc, e);
-        (rightVal, pc)
+        let (rightVal, pc) = evaluate(&exp.body[2], pc, e)?;
+        Ok((rightVal, pc))
       }
     },
     ExpressionTag::LET => {
       let name : &String = &exp.name;
-      let (value, pc) = evaluate(&exp.body[0], pc, e);
+      let (value, pc) = evaluate(&exp.body[0], pc, e)?;
 
       let newE = e.bind(name.to_string(), value);
 
-      let (res, pc) = evaluate(&exp.body[1], pc, &newE);
-      (res, pc)
+      let (res, pc) = evaluate(&exp.body[1], pc, &newE)?;
+      Ok((res, pc))
     },
     ExpressionTag::VARIABLE => {
       let name : &String = &exp.name;
       let value = e.lookup(name.to_string());
-      (value, pc)
+      if value.isNull() {
+        return Err(RuntimeError::new(pc, RuntimeErrorKind::UnboundVariable, format!("variable '{}' is not bound", name)));
+      }
+      Ok((value, pc))
     },
     ExpressionTag::FUNC_DECLARATION => {
       let formalArgNames : Vec<String> = Vec::clone(&exp.argNames);
       let funcBody : Expression = Expression::clone(&exp.body[0]);
 
-      let retVal : Value = Value::function(funcBody, formalArgNames);
-      (retVal, pc)
+      let retVal : Value = Value::function(funcBody, formalArgNames, Environment::clone(e));
+      Ok((retVal, pc))
     },
     ExpressionTag::FUNC_CALL => {
       let name : &String = &exp.name;
       let functionValue = e.lookup(name.to_string());
+      if functionValue.tag != ValueTag::FUNC {
+        return Err(RuntimeError::new(pc, RuntimeErrorKind::NotAFunction, format!("'{}' is not bound to a function", name)));
+      }
       let theFunction : Expression = functionValue.funcVal;
       let formalArgNames : Vec<String> = functionValue.formalFuncArgNames;
 
-      let mut evalEnv : Environment = Environment::clone(e);
+      if exp.body.len() != formalArgNames.len() {
+        return Err(RuntimeError::new(pc, RuntimeErrorKind::ArityMismatch, format!("'{}' expects {} argument(s), got {}", name, formalArgNames.len(), exp.body.len())));
+      }
+
+      // Arguments are evaluated in the caller's environment, but the body
+      // runs against the function's captured (defining) environment, so
+      // free variables resolve lexically instead of dynamically.
+      let mut evalEnv : Environment = Environment::clone(&functionValue.capturedEnv);
+      let mut pc = pc;
       for i in 0..exp.body.len() {
-        let (thisArgValue, pc) = evaluate(&exp.body[i], pc, &e);
+        let (thisArgValue, newPc) = evaluate(&exp.body[i], pc, &e)?;
+        pc = newPc;
         evalEnv = evalEnv.bind(formalArgNames[i].to_string(),thisArgValue);
       }
 
-      let (ret, pc) = evaluate(&theFunction, pc, &evalEnv);
-      (ret, pc)
+      let (ret, pc) = evaluate(&theFunction, pc, &evalEnv)?;
+      Ok((ret, pc))
+    },
+  }
+}
+
+//==================//
+// Parser Definition //
+//==================//
+// Reads concrete syntax like:
+//   let bot = 3 in bot + f(400+74, bot)
+//   if a == b then x else y
+//   fn(top,bot) => if (bot == 0) then 0 else top/bot
+// and produces the same Expression tree the constructors above build by hand.
+mod parser {
+  use super::{ArithmeticOperator, ComparisonOperator, Expression};
+
+  //===========//
+  // Tokenizer //
+  //===========//
+  #[derive(Clone, PartialEq, Debug)]
+  enum Token {
+    INT(i32),
+    BOOL(bool),
+    IDENT(String),
+    LET,
+    IN,
+    IF,
+    THEN,
+    ELSE,
+    FN,
+    ARROW,
+    PLUS,
+    MINUS,
+    STAR,
+    SLASH,
+    EQ,
+    EQEQ,
+    LPAREN,
+    RPAREN,
+    COMMA,
+  }
+
+  fn tokenize(src: &str) -> Vec<Token> {
+    let chars : Vec<char> = src.chars().collect();
+    let mut tokens : Vec<Token> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+      let c = chars[i];
+
+      if c.is_whitespace() {
+        i += 1;
+      }
+      else if c.is_ascii_digit() {
+        let mut j = i;
+        while j < chars.len() && chars[j].is_ascii_digit() {
+          j += 1;
+        }
+        let text : String = chars[i..j].iter().collect();
+        tokens.push(Token::INT(text.parse::<i32>().unwrap()));
+        i = j;
+      }
+      else if c.is_alphabetic() || c == '_' {
+        let mut j = i;
+        while j < chars.len() && (chars[j].is_alphanumeric() || chars[j] == '_') {
+          j += 1;
+        }
+        let word : String = chars[i..j].iter().collect();
+        let tok = match word.as_str() {
+          "let"   => Token::LET,
+          "in"    => Token::IN,
+          "if"    => Token::IF,
+          "then"  => Token::THEN,
+          "else"  => Token::ELSE,
+          "fn"    => Token::FN,
+          "true"  => Token::BOOL(true),
+          "false" => Token::BOOL(false),
+          _       => Token::IDENT(word),
+        };
+        tokens.push(tok);
+        i = j;
+      }
+      else {
+        match c {
+          '+' => { tokens.push(Token::PLUS); i += 1; },
+          '-' => {
+            if i + 1 < chars.len() && chars[i+1] == '>' {
+              tokens.push(Token::ARROW);
+              i += 2;
+            } else {
+              tokens.push(Token::MINUS);
+              i += 1;
+            }
+          },
+          '*' => { tokens.push(Token::STAR); i += 1; },
+          '/' => { tokens.push(Token::SLASH); i += 1; },
+          '(' => { tokens.push(Token::LPAREN); i += 1; },
+          ')' => { tokens.push(Token::RPAREN); i += 1; },
+          ',' => { tokens.push(Token::COMMA); i += 1; },
+          '=' => {
+            if i + 1 < chars.len() && chars[i+1] == '=' {
+              tokens.push(Token::EQEQ);
+              i += 2;
+            }
+            else if i + 1 < chars.len() && chars[i+1] == '>' {
+              tokens.push(Token::ARROW);
+              i += 2;
+            }
+            else {
+              tokens.push(Token::EQ);
+              i += 1;
+            }
+          },
+          _ => panic!("parser: unexpected character '{}'", c),
+        }
+      }
+    }
+
+    tokens
+  }
+
+  //========//
+  // Parser //
+  //========//
+  // Precedence-climbing expression parser. `* /` binds tighter than `+ -`,
+  // which binds tighter than `==`; all three are left-associative.
+  struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+  }
+  impl Parser {
+    fn peek(&self) -> Option<&Token> {
+      self.tokens.get(self.pos)
+    }
+    fn advance(&mut self) -> Token {
+      let tok = self.tokens[self.pos].clone();
+      self.pos += 1;
+      tok
+    }
+    fn expect(&mut self, expected: Token) {
+      let tok = self.advance();
+      if tok != expected {
+        panic!("parser: expected {:?}, found {:?}", expected, tok);
+      }
+    }
+
+    fn parseExpr(&mut self, minPrecedence: u8) -> Expression {
+      let mut left = self.parsePrimary();
+
+      loop {
+        let (opPrecedence, isArith) = match self.peek() {
+          Some(Token::STAR) | Some(Token::SLASH) => (2, true),
+          Some(Token::PLUS) | Some(Token::MINUS) => (1, true),
+          Some(Token::EQEQ) => (0, false),
+          _ => break,
+        };
+        if opPrecedence < minPrecedence {
+          break;
+        }
+
+        let opTok = self.advance();
+        let right = self.parseExpr(opPrecedence + 1);
+
+        left = if isArith {
+          let op = match opTok {
+            Token::PLUS  => ArithmeticOperator::PLUS,
+            Token::MINUS => ArithmeticOperator::MINUS,
+            Token::STAR  => ArithmeticOperator::TIMES,
+            Token::SLASH => ArithmeticOperator::DIV,
+            _ => unreachable!(),
+          };
+          Expression::binaryOperation(op, left, right)
+        } else {
+          Expression::comparison(ComparisonOperator::EQ, left, right)
+        };
+      }
+
+      left
+    }
+
+    fn parsePrimary(&mut self) -> Expression {
+      match self.advance() {
+        Token::INT(value) => Expression::integerConstant(value),
+        Token::BOOL(value) => Expression::booleanConstant(value),
+        Token::LPAREN => {
+          let inner = self.parseExpr(0);
+          self.expect(Token::RPAREN);
+          inner
+        },
+        Token::IF => {
+          let cond = self.parseExpr(0);
+          self.expect(Token::THEN);
+          let thenSide = self.parseExpr(0);
+          self.expect(Token::ELSE);
+          let elseSide = self.parseExpr(0);
+          Expression::ifBranch(cond, thenSide, elseSide)
+        },
+        Token::LET => {
+          let name = self.expectIdent();
+          self.expect(Token::EQ);
+          let value = self.parseExpr(0);
+          self.expect(Token::IN);
+          let body = self.parseExpr(0);
+          Expression::assignVariable(name, value, body)
+        },
+        Token::FN => {
+          self.expect(Token::LPAREN);
+          let mut argNames : Vec<String> = Vec::new();
+          if self.peek() != Some(&Token::RPAREN) {
+            argNames.push(self.expectIdent());
+            while self.peek() == Some(&Token::COMMA) {
+              self.advance();
+              argNames.push(self.expectIdent());
+            }
+          }
+          self.expect(Token::RPAREN);
+          self.expect(Token::ARROW);
+          let body = self.parseExpr(0);
+          Expression::functionDeclaration(body, argNames)
+        },
+        Token::IDENT(name) => {
+          if self.peek() == Some(&Token::LPAREN) {
+            self.advance();
+            let mut actualArgs : Vec<Expression> = Vec::new();
+            if self.peek() != Some(&Token::RPAREN) {
+              actualArgs.push(self.parseExpr(0));
+              while self.peek() == Some(&Token::COMMA) {
+                self.advance();
+                actualArgs.push(self.parseExpr(0));
+              }
+            }
+            self.expect(Token::RPAREN);
+            Expression::functionCall(name, actualArgs)
+          } else {
+            Expression::readVariable(name)
+          }
+        },
+        tok => panic!("parser: unexpected token {:?}", tok),
+      }
+    }
+
+    fn expectIdent(&mut self) -> String {
+      match self.advance() {
+        Token::IDENT(name) => name,
+        tok => panic!("parser: expected identifier, found {:?}", tok),
+      }
+    }
+  }
+
+  // Parses a full program from source text into the same Expression tree
+  // the hand-built constructors produce, so it plugs directly into evaluate.
+  pub fn parse(src: &str) -> Expression {
+    let mut p = Parser {
+      tokens: tokenize(src),
+      pos: 0,
+    };
+    p.parseExpr(0)
+  }
+}
+
+//===============//
+// VM Definition //
+//===============//
+// Compiles an Expression into a flat Vec<OpCode> and executes it against an
+// operand stack, so running a large program no longer recurses through
+// Rust's call stack one frame per AST node (compilation still does).
+mod vm {
+  use super::{ArithmeticOperator, ComparisonOperator, Expression, ExpressionTag, RuntimeError, RuntimeErrorKind, Value};
+
+  #[derive(Clone, Debug)]
+  enum OpCode {
+    PushInt(i32),
+    PushBool(bool),
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Jump(usize),
+    JumpIfFalse(usize),
+    LoadVar(String),
+    StoreVar(String),
+    PopVar,
+    MakeClosure { body_addr: usize, args: Vec<String> },
+    Call(usize),
+    Return,
+  }
+
+  #[derive(Clone)]
+  enum VmValue {
+    Int(i32),
+    Bool(bool),
+    Closure { body_addr: usize, args: Vec<String>, env: Vec<(String, VmValue)> },
+  }
+
+  //==========//
+  // Compiler //
+  //==========//
+  struct Compiler {
+    code: Vec<OpCode>,
+    // (placeholder index of the MakeClosure op, formal args, body) for
+    // function bodies that still need to be compiled and backpatched.
+    pendingFunctions: Vec<(usize, Vec<String>, Expression)>,
+  }
+  impl Compiler {
+    fn emit(&mut self, op: OpCode) -> usize {
+      self.code.push(op);
+      self.code.len() - 1
+    }
+
+    fn compileExpr(&mut self, exp: &Expression) -> Result<(), RuntimeError> {
+      match exp.tag {
+        ExpressionTag::INT_CONST => {
+          self.emit(OpCode::PushInt(exp.intConst));
+        },
+        ExpressionTag::BOOL_CONST => {
+          self.emit(OpCode::PushBool(exp.boolConst));
+        },
+        ExpressionTag::STRING_CONST | ExpressionTag::ARRAY_LIT | ExpressionTag::INDEX => {
+          return Err(RuntimeError::new(0, RuntimeErrorKind::TypeMismatch, "vm: this backend does not yet compile STRING/ARRAY expressions".to_string()));
+        },
+        ExpressionTag::BIN_OP => {
+          self.compileExpr(&exp.body[0])?;
+          self.compileExpr(&exp.body[1])?;
+          let op = match exp.op {
+            ArithmeticOperator::PLUS  => OpCode::Add,
+            ArithmeticOperator::MINUS => OpCode::Sub,
+            ArithmeticOperator::TIMES => OpCode::Mul,
+            ArithmeticOperator::DIV   => OpCode::Div,
+            _ => return Err(RuntimeError::new(0, RuntimeErrorKind::TypeMismatch, "vm: this backend only compiles PLUS/MINUS/TIMES/DIV".to_string())),
+          };
+          self.emit(op);
+        },
+        ExpressionTag::COMP => {
+          if !matches!(exp.comp, ComparisonOperator::EQ) {
+            return Err(RuntimeError::new(0, RuntimeErrorKind::TypeMismatch, "vm: this backend only compiles EQ comparisons".to_string()));
+          }
+          self.compileExpr(&exp.body[0])?;
+          self.compileExpr(&exp.body[1])?;
+          self.emit(OpCode::Eq);
+        },
+        ExpressionTag::IF => {
+          self.compileExpr(&exp.body[0])?;
+          let jumpIfFalseIdx = self.emit(OpCode::JumpIfFalse(0));
+          self.compileExpr(&exp.body[1])?;
+          let jumpIdx = self.emit(OpCode::Jump(0));
+          let elseAddr = self.code.len();
+          self.code[jumpIfFalseIdx] = OpCode::JumpIfFalse(elseAddr);
+          self.compileExpr(&exp.body[2])?;
+          let endAddr = self.code.len();
+          self.code[jumpIdx] = OpCode::Jump(endAddr);
+        },
+        ExpressionTag::LET => {
+          self.compileExpr(&exp.body[0])?;
+          self.emit(OpCode::StoreVar(exp.name.clone()));
+          self.compileExpr(&exp.body[1])?;
+          self.emit(OpCode::PopVar);
+        },
+        ExpressionTag::VARIABLE => {
+          self.emit(OpCode::LoadVar(exp.name.clone()));
+        },
+        ExpressionTag::FUNC_DECLARATION => {
+          let placeholder = self.emit(OpCode::MakeClosure { body_addr: 0, args: Vec::new() });
+          self.pendingFunctions.push((placeholder, exp.argNames.clone(), Expression::clone(&exp.body[0])));
+        },
+        ExpressionTag::FUNC_CALL => {
+          for arg in &exp.body {
+            self.compileExpr(arg)?;
+          }
+          self.emit(OpCode::LoadVar(exp.name.clone()));
+          self.emit(OpCode::Call(exp.body.len()));
+        },
+      }
+      Ok(())
+    }
+
+    // Compiles every function body discovered while compiling the main
+    // program (and any function bodies those bodies themselves declare),
+    // appending each after the main code and backpatching its MakeClosure.
+    fn compilePendingFunctions(&mut self) -> Result<(), RuntimeError> {
+      let mut i = 0;
+      while i < self.pendingFunctions.len() {
+        let (placeholder, argNames, body) = self.pendingFunctions[i].clone();
+        let bodyAddr = self.code.len();
+        self.compileExpr(&body)?;
+        self.emit(OpCode::Return);
+        self.code[placeholder] = OpCode::MakeClosure { body_addr: bodyAddr, args: argNames };
+        i += 1;
+      }
+      Ok(())
+    }
+  }
+
+  // Compiles a full program. The returned code ends with a `Return` marking
+  // the end of the main program, followed by the bodies of any functions
+  // declared within it. Returns a `RuntimeError` rather than panicking when
+  // the program uses a construct this backend does not yet support.
+  fn compile(expr: &Expression) -> Result<Vec<OpCode>, RuntimeError> {
+    let mut compiler = Compiler {
+      code: Vec::new(),
+      pendingFunctions: Vec::new(),
+    };
+    compiler.compileExpr(expr)?;
+    compiler.emit(OpCode::Return);
+    compiler.compilePendingFunctions()?;
+    Ok(compiler.code)
+  }
+
+  //=========//
+  // Machine //
+  //=========//
+  struct CallFrame {
+    returnAddr: usize,
+    savedScope: Vec<(String, VmValue)>,
+  }
+
+  fn lookup(scope: &Vec<(String, VmValue)>, name: &str) -> Option<VmValue> {
+    for i in (0..scope.len()).rev() {
+      if scope[i].0 == name {
+        return Some(scope[i].1.clone());
+      }
+    }
+    None
+  }
+
+  fn run(code: &Vec<OpCode>) -> Result<Value, RuntimeError> {
+    let mut pc : usize = 0;
+    let mut stack : Vec<VmValue> = Vec::new();
+    let mut scope : Vec<(String, VmValue)> = Vec::new();
+    let mut callStack : Vec<CallFrame> = Vec::new();
+
+    loop {
+      let op = &code[pc];
+      pc += 1;
+
+      match op {
+        OpCode::PushInt(value) => stack.push(VmValue::Int(*value)),
+        OpCode::PushBool(value) => stack.push(VmValue::Bool(*value)),
+        OpCode::Add | OpCode::Sub | OpCode::Mul | OpCode::Div => {
+          let right = stack.pop().unwrap();
+          let left = stack.pop().unwrap();
+          let (l, r) = match (left, right) {
+            (VmValue::Int(l), VmValue::Int(r)) => (l, r),
+            _ => return Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, "arithmetic operands must both be INT".to_string())),
+          };
+          let result = match op {
+            OpCode::Add => l + r,
+            OpCode::Sub => l - r,
+            OpCode::Mul => l * r,
+            OpCode::Div => {
+              if r == 0 {
+                return Err(RuntimeError::new(pc, RuntimeErrorKind::DivByZero, "division by zero".to_string()));
+              }
+              l / r
+            },
+            _ => unreachable!(),
+          };
+          stack.push(VmValue::Int(result));
+        },
+        OpCode::Eq => {
+          let right = stack.pop().unwrap();
+          let left = stack.pop().unwrap();
+          let result = match (left, right) {
+            (VmValue::Int(l), VmValue::Int(r)) => l == r,
+            (VmValue::Bool(l), VmValue::Bool(r)) => l == r,
+            _ => false,
+          };
+          stack.push(VmValue::Bool(result));
+        },
+        OpCode::Jump(target) => pc = *target,
+        OpCode::JumpIfFalse(target) => {
+          let cond = stack.pop().unwrap();
+          match cond {
+            VmValue::Bool(false) => pc = *target,
+            VmValue::Bool(true) => (),
+            _ => return Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, "if condition must be BOOL".to_string())),
+          }
+        },
+        OpCode::LoadVar(name) => {
+          match lookup(&scope, name) {
+            Some(value) => stack.push(value),
+            None => return Err(RuntimeError::new(pc, RuntimeErrorKind::UnboundVariable, format!("variable '{}' is not bound", name))),
+          }
+        },
+        OpCode::StoreVar(name) => {
+          let value = stack.pop().unwrap();
+          scope.push((name.clone(), value));
+        },
+        OpCode::PopVar => {
+          scope.pop();
+        },
+        OpCode::MakeClosure { body_addr, args } => {
+          stack.push(VmValue::Closure { body_addr: *body_addr, args: args.clone(), env: scope.clone() });
+        },
+        OpCode::Call(argc) => {
+          let callee = stack.pop().unwrap();
+          let (bodyAddr, formalArgs, capturedEnv) = match callee {
+            VmValue::Closure { body_addr, args, env } => (body_addr, args, env),
+            _ => return Err(RuntimeError::new(pc, RuntimeErrorKind::NotAFunction, "call target is not a function".to_string())),
+          };
+          if formalArgs.len() != *argc {
+            return Err(RuntimeError::new(pc, RuntimeErrorKind::ArityMismatch, format!("expected {} argument(s), got {}", formalArgs.len(), argc)));
+          }
+          let mut actualArgs : Vec<VmValue> = Vec::new();
+          for _ in 0..*argc {
+            actualArgs.push(stack.pop().unwrap());
+          }
+          actualArgs.reverse();
+
+          callStack.push(CallFrame { returnAddr: pc, savedScope: scope });
+          scope = capturedEnv;
+          for (name, value) in formalArgs.into_iter().zip(actualArgs.into_iter()) {
+            scope.push((name, value));
+          }
+          pc = bodyAddr;
+        },
+        OpCode::Return => {
+          let retVal = stack.pop().unwrap();
+          match callStack.pop() {
+            Some(frame) => {
+              scope = frame.savedScope;
+              pc = frame.returnAddr;
+              stack.push(retVal);
+            },
+            None => {
+              return match retVal {
+                VmValue::Int(value) => Ok(Value::integer(value)),
+                VmValue::Bool(value) => Ok(Value::boolean(value)),
+                VmValue::Closure { .. } => Err(RuntimeError::new(pc, RuntimeErrorKind::TypeMismatch, "program result cannot be a function value".to_string())),
+              };
+            },
+          }
+        },
+      }
+    }
+  }
+
+  // Compiles and runs a program end-to-end, reporting failures through the
+  // same RuntimeError as `evaluate` but executing against a bytecode VM
+  // instead of the Rust call stack.
+  pub fn interpret(expr: &Expression) -> Result<Value, RuntimeError> {
+    let code = compile(expr)?;
+    run(&code)
+  }
+}
+
+//======================//
+// Analysis Definition //
+//======================//
+// A single static-analysis finding, e.g. an unbound variable or an arity
+// mismatch caught by walking the tree once without executing it.
+#[derive(Clone)]
+struct AnalysisError {
+  message: String,
+}
+impl AnalysisError {
+  fn new(message: String) -> AnalysisError {
+    AnalysisError {
+      message: message,
+    }
+  }
+}
+// Print Function
+impl std::fmt::Display for AnalysisError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "AnalysisError: {}", self.message)
+  }
+}
+
+// One lexical scope's worth of statically-known names. `funcArity` and
+// `nonFuncNames` only record what can be determined without running the
+// program: a name bound directly to a FUNC_DECLARATION, or directly to a
+// plain INT_CONST/BOOL_CONST. Anything else (e.g. a name bound to another
+// variable or to a function's own parameter) is left unclassified.
+struct AnalysisScope {
+  names: Vec<String>,
+  funcArity: Vec<(String, usize)>,
+  nonFuncNames: Vec<String>,
+}
+impl AnalysisScope {
+  fn new() -> AnalysisScope {
+    AnalysisScope {
+      names: Vec::new(),
+      funcArity: Vec::new(),
+      nonFuncNames: Vec::new(),
+    }
+  }
+}
+
+fn isBound(scopes: &Vec<AnalysisScope>, name: &str) -> bool {
+  scopes.iter().rev().any(|frame| frame.names.iter().any(|n| n == name))
+}
+fn lookupArity(scopes: &Vec<AnalysisScope>, name: &str) -> Option<usize> {
+  for frame in scopes.iter().rev() {
+    for (n, arity) in frame.funcArity.iter().rev() {
+      if n == name {
+        return Some(*arity);
+      }
+    }
+    if frame.names.iter().any(|n| n == name) {
+      return None;
+    }
+  }
+  None
+}
+fn isKnownNonFunction(scopes: &Vec<AnalysisScope>, name: &str) -> bool {
+  for frame in scopes.iter().rev() {
+    if frame.nonFuncNames.iter().any(|n| n == name) {
+      return true;
+    }
+    if frame.funcArity.iter().any(|(n, _)| n == name) {
+      return false;
+    }
+    if frame.names.iter().any(|n| n == name) {
+      return false;
+    }
+  }
+  false
+}
+
+fn analyzeExpr(exp: &Expression, scopes: &mut Vec<AnalysisScope>, errors: &mut Vec<AnalysisError>) {
+  match exp.tag {
+    ExpressionTag::INT_CONST | ExpressionTag::BOOL_CONST | ExpressionTag::STRING_CONST => {},
+    ExpressionTag::ARRAY_LIT => {
+      for element in &exp.body {
+        analyzeExpr(element, scopes, errors);
+      }
+    },
+    ExpressionTag::INDEX => {
+      analyzeExpr(&exp.body[0], scopes, errors);
+      analyzeExpr(&exp.body[1], scopes, errors);
     },
+    ExpressionTag::BIN_OP | ExpressionTag::COMP => {
+      analyzeExpr(&exp.body[0], scopes, errors);
+      analyzeExpr(&exp.body[1], scopes, errors);
+    },
+    ExpressionTag::IF => {
+      analyzeExpr(&exp.body[0], scopes, errors);
+      analyzeExpr(&exp.body[1], scopes, errors);
+      analyzeExpr(&exp.body[2], scopes, errors);
+    },
+    ExpressionTag::LET => {
+      // The bound value is analyzed in the outer scope: this interpreter
+      // has no recursive let, so `name` isn't visible to its own value.
+      analyzeExpr(&exp.body[0], scopes, errors);
+
+      let mut frame = AnalysisScope::new();
+      frame.names.push(exp.name.clone());
+      match exp.body[0].tag {
+        ExpressionTag::FUNC_DECLARATION => frame.funcArity.push((exp.name.clone(), exp.body[0].argNames.len())),
+        ExpressionTag::INT_CONST | ExpressionTag::BOOL_CONST => frame.nonFuncNames.push(exp.name.clone()),
+        _ => {},
+      }
+      scopes.push(frame);
+      analyzeExpr(&exp.body[1], scopes, errors);
+      scopes.pop();
+    },
+    ExpressionTag::VARIABLE => {
+      if !isBound(scopes, &exp.name) {
+        errors.push(AnalysisError::new(format!("unbound variable '{}'", exp.name)));
+      }
+    },
+    ExpressionTag::FUNC_DECLARATION => {
+      let mut frame = AnalysisScope::new();
+      frame.names = exp.argNames.clone();
+      scopes.push(frame);
+      analyzeExpr(&exp.body[0], scopes, errors);
+      scopes.pop();
+    },
+    ExpressionTag::FUNC_CALL => {
+      if !isBound(scopes, &exp.name) {
+        errors.push(AnalysisError::new(format!("call to unbound function '{}'", exp.name)));
+      }
+      else if isKnownNonFunction(scopes, &exp.name) {
+        errors.push(AnalysisError::new(format!("'{}' is not bound to a function", exp.name)));
+      }
+      else if let Some(arity) = lookupArity(scopes, &exp.name) {
+        if arity != exp.body.len() {
+          errors.push(AnalysisError::new(format!("'{}' expects {} argument(s), got {}", exp.name, arity, exp.body.len())));
+        }
+      }
+      for arg in &exp.body {
+        analyzeExpr(arg, scopes, errors);
+      }
+    },
+  }
+}
+
+// Walks the tree once, collecting every finding instead of stopping at the
+// first one, so callers can report all static mistakes in a single pass.
+fn analyze(expr: &Expression) -> Vec<AnalysisError> {
+  let mut scopes : Vec<AnalysisScope> = vec![AnalysisScope::new()];
+  let mut errors : Vec<AnalysisError> = Vec::new();
+  analyzeExpr(expr, &mut scopes, &mut errors);
+  errors
+}
+
+//=======================//
+// Optimize Definition //
+//=======================//
+// Counts how many times `name` is read as a VARIABLE within `exp`, treating
+// a LET or FUNC_DECLARATION that rebinds `name` as shadowing it for the rest
+// of that subtree.
+fn countUses(exp: &Expression, name: &str) -> usize {
+  match exp.tag {
+    ExpressionTag::INT_CONST | ExpressionTag::BOOL_CONST | ExpressionTag::STRING_CONST => 0,
+    ExpressionTag::ARRAY_LIT => exp.body.iter().map(|a| countUses(a, name)).sum(),
+    ExpressionTag::VARIABLE => if exp.name == name { 1 } else { 0 },
+    ExpressionTag::BIN_OP | ExpressionTag::COMP | ExpressionTag::INDEX => countUses(&exp.body[0], name) + countUses(&exp.body[1], name),
+    ExpressionTag::IF => countUses(&exp.body[0], name) + countUses(&exp.body[1], name) + countUses(&exp.body[2], name),
+    ExpressionTag::LET => {
+      let valueUses = countUses(&exp.body[0], name);
+      if exp.name == name {
+        valueUses
+      } else {
+        valueUses + countUses(&exp.body[1], name)
+      }
+    },
+    ExpressionTag::FUNC_DECLARATION => {
+      if exp.argNames.iter().any(|a| a == name) {
+        0
+      } else {
+        countUses(&exp.body[0], name)
+      }
+    },
+    ExpressionTag::FUNC_CALL => exp.body.iter().map(|a| countUses(a, name)).sum(),
+  }
+}
+
+// Replaces every free read of `name` within `exp` with `value`, stopping at
+// any LET or FUNC_DECLARATION that rebinds `name` (mirrors countUses).
+fn substitute(exp: Expression, name: &str, value: &Expression) -> Expression {
+  match exp.tag {
+    ExpressionTag::INT_CONST | ExpressionTag::BOOL_CONST | ExpressionTag::STRING_CONST => exp,
+    ExpressionTag::ARRAY_LIT => {
+      let elements = exp.body.into_iter().map(|e| substitute(e, name, value)).collect();
+      Expression::arrayLiteral(elements)
+    },
+    ExpressionTag::INDEX => {
+      let mut it = exp.body.into_iter();
+      let collection = substitute(it.next().unwrap(), name, value);
+      let indexExpr = substitute(it.next().unwrap(), name, value);
+      Expression::index(collection, indexExpr)
+    },
+    ExpressionTag::VARIABLE => {
+      if exp.name == name {
+        Expression::clone(value)
+      } else {
+        exp
+      }
+    },
+    ExpressionTag::BIN_OP => {
+      let mut it = exp.body.into_iter();
+      let left = substitute(it.next().unwrap(), name, value);
+      let right = substitute(it.next().unwrap(), name, value);
+      Expression::binaryOperation(exp.op, left, right)
+    },
+    ExpressionTag::COMP => {
+      let mut it = exp.body.into_iter();
+      let left = substitute(it.next().unwrap(), name, value);
+      let right = substitute(it.next().unwrap(), name, value);
+      Expression::comparison(exp.comp, left, right)
+    },
+    ExpressionTag::IF => {
+      let mut it = exp.body.into_iter();
+      let cond = substitute(it.next().unwrap(), name, value);
+      let thenSide = substitute(it.next().unwrap(), name, value);
+      let elseSide = substitute(it.next().unwrap(), name, value);
+      Expression::ifBranch(cond, thenSide, elseSide)
+    },
+    ExpressionTag::LET => {
+      let varName = exp.name.clone();
+      let mut it = exp.body.into_iter();
+      let boundValue = substitute(it.next().unwrap(), name, value);
+      let letBody = it.next().unwrap();
+      let newBody = if varName == name {
+        letBody
+      } else {
+        substitute(letBody, name, value)
+      };
+      Expression::assignVariable(varName, boundValue, newBody)
+    },
+    ExpressionTag::FUNC_DECLARATION => {
+      if exp.argNames.iter().any(|a| a == name) {
+        exp
+      } else {
+        let argNames = exp.argNames.clone();
+        let mut it = exp.body.into_iter();
+        let funcBody = substitute(it.next().unwrap(), name, value);
+        Expression::functionDeclaration(funcBody, argNames)
+      }
+    },
+    ExpressionTag::FUNC_CALL => {
+      let callName = exp.name.clone();
+      let newArgs = exp.body.into_iter().map(|a| substitute(a, name, value)).collect();
+      Expression::functionCall(callName, newArgs)
+    },
+  }
+}
+
+// Folds a BIN_OP of two INT_CONST operands, returning None where the result
+// isn't statically known-safe (e.g. a constant-zero divisor, or an overflow
+// that `evaluate` itself would never hit because the branch is unreachable
+// at runtime), so the node is left unfolded and `evaluate`'s runtime error
+// semantics still fire instead of this pass panicking on debug overflow.
+fn foldArithmetic(op: ArithmeticOperator, left: i32, right: i32) -> Option<i32> {
+  match op {
+    ArithmeticOperator::PLUS   => left.checked_add(right),
+    ArithmeticOperator::MINUS => left.checked_sub(right),
+    ArithmeticOperator::TIMES => left.checked_mul(right),
+    ArithmeticOperator::DIV   => if right != 0 { left.checked_div(right) } else { None },
+    ArithmeticOperator::MOD   => if right != 0 { left.checked_rem(right) } else { None },
+    ArithmeticOperator::POW   => if right >= 0 { left.checked_pow(right as u32) } else { None },
+    ArithmeticOperator::BITAND => Some(left & right),
+    ArithmeticOperator::BITOR  => Some(left | right),
+    ArithmeticOperator::BITXOR => Some(left ^ right),
+    ArithmeticOperator::SHL    => if right >= 0 && right < 32 { Some(left << right) } else { None },
+    ArithmeticOperator::SHR    => if right >= 0 && right < 32 { Some(left >> right) } else { None },
+    ArithmeticOperator::NONE   => None,
+  }
+}
+
+// One bottom-up optimization pass. Returns the (possibly rewritten) tree
+// along with whether anything changed, so `optimize` can iterate to a
+// fixpoint instead of relying on a single pass to fully reduce nested
+// constants like `(400+74)/3`.
+fn optimizeStep(exp: Expression) -> (Expression, bool) {
+  match exp.tag {
+    ExpressionTag::INT_CONST | ExpressionTag::BOOL_CONST | ExpressionTag::STRING_CONST | ExpressionTag::VARIABLE => (exp, false),
+    ExpressionTag::ARRAY_LIT => {
+      let mut changed = false;
+      let mut elements : Vec<Expression> = Vec::new();
+      for element in exp.body {
+        let (newElement, c) = optimizeStep(element);
+        changed = changed || c;
+        elements.push(newElement);
+      }
+      (Expression::arrayLiteral(elements), changed)
+    },
+    ExpressionTag::INDEX => {
+      let mut it = exp.body.into_iter();
+      let (collection, c1) = optimizeStep(it.next().unwrap());
+      let (indexExpr, c2) = optimizeStep(it.next().unwrap());
+      (Expression::index(collection, indexExpr), c1 || c2)
+    },
+    ExpressionTag::BIN_OP => {
+      let op = exp.op;
+      let mut it = exp.body.into_iter();
+      let (left, c1) = optimizeStep(it.next().unwrap());
+      let (right, c2) = optimizeStep(it.next().unwrap());
+      let mut changed = c1 || c2;
+
+      if let (ExpressionTag::INT_CONST, ExpressionTag::INT_CONST) = (left.tag, right.tag) {
+        if let Some(folded) = foldArithmetic(op, left.intConst, right.intConst) {
+          return (Expression::integerConstant(folded), true);
+        }
+      }
+      (Expression::binaryOperation(op, left, right), changed)
+    },
+    ExpressionTag::COMP => {
+      let comp = exp.comp;
+      let mut it = exp.body.into_iter();
+      let (left, c1) = optimizeStep(it.next().unwrap());
+      let (right, c2) = optimizeStep(it.next().unwrap());
+      let changed = c1 || c2;
+
+      let folded = match (left.tag, right.tag) {
+        (ExpressionTag::INT_CONST, ExpressionTag::INT_CONST) => {
+          let (l, r) = (left.intConst, right.intConst);
+          match comp {
+            ComparisonOperator::EQ  => Some(l == r),
+            ComparisonOperator::NEQ => Some(l != r),
+            ComparisonOperator::LT  => Some(l < r),
+            ComparisonOperator::LE  => Some(l <= r),
+            ComparisonOperator::GT  => Some(l > r),
+            ComparisonOperator::GE  => Some(l >= r),
+            ComparisonOperator::NONE => None,
+          }
+        },
+        (ExpressionTag::BOOL_CONST, ExpressionTag::BOOL_CONST) => {
+          let (l, r) = (left.boolConst, right.boolConst);
+          match comp {
+            ComparisonOperator::EQ  => Some(l == r),
+            ComparisonOperator::NEQ => Some(l != r),
+            _ => None,
+          }
+        },
+        _ => None,
+      };
+      match folded {
+        Some(value) => (Expression::booleanConstant(value), true),
+        None => (Expression::comparison(comp, left, right), changed),
+      }
+    },
+    ExpressionTag::IF => {
+      let mut it = exp.body.into_iter();
+      let (cond, c0) = optimizeStep(it.next().unwrap());
+      let (thenSide, c1) = optimizeStep(it.next().unwrap());
+      let (elseSide, c2) = optimizeStep(it.next().unwrap());
+
+      if let ExpressionTag::BOOL_CONST = cond.tag {
+        let taken = if cond.boolConst { thenSide } else { elseSide };
+        return (taken, true);
+      }
+      (Expression::ifBranch(cond, thenSide, elseSide), c0 || c1 || c2)
+    },
+    ExpressionTag::LET => {
+      let varName = exp.name.clone();
+      let mut it = exp.body.into_iter();
+      let (value, c1) = optimizeStep(it.next().unwrap());
+      let (body, c2) = optimizeStep(it.next().unwrap());
+      let changed = c1 || c2;
+
+      let isConstant = matches!(value.tag, ExpressionTag::INT_CONST | ExpressionTag::BOOL_CONST);
+      if isConstant && countUses(&body, &varName) <= 1 {
+        return (substitute(body, &varName, &value), true);
+      }
+      (Expression::assignVariable(varName, value, body), changed)
+    },
+    ExpressionTag::FUNC_DECLARATION => {
+      let argNames = exp.argNames.clone();
+      let mut it = exp.body.into_iter();
+      let (body, changed) = optimizeStep(it.next().unwrap());
+      (Expression::functionDeclaration(body, argNames), changed)
+    },
+    ExpressionTag::FUNC_CALL => {
+      let callName = exp.name.clone();
+      let mut changed = false;
+      let mut newArgs : Vec<Expression> = Vec::new();
+      for arg in exp.body {
+        let (newArg, c) = optimizeStep(arg);
+        changed = changed || c;
+        newArgs.push(newArg);
+      }
+      (Expression::functionCall(callName, newArgs), changed)
+    },
+  }
+}
+
+// Constant-folds and dead-branch-eliminates `expr`, iterating to a fixpoint
+// so nested constants fully reduce before the tree reaches `evaluate`.
+fn optimize(expr: Expression) -> Expression {
+  let mut current = expr;
+  loop {
+    let (next, changed) = optimizeStep(current);
+    current = next;
+    if !changed {
+      return current;
+    }
   }
 }
 
@@ -666,6 +1902,187 @@ fn main() {
     )
   );
 
-  let (res, pc) = evaluate(&p6, 0, &Environment::new());
-  print!(">>> Result: {} | PC: {}\n", res, pc);
+  match evaluate(&p6, 0, &Environment::new()) {
+    Ok((res, pc)) => print!(">>> Result: {} | PC: {}\n", res, pc),
+    Err(e) => print!(">>> {}\n", e),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Parsing source text should produce a tree that evaluates the same as
+  // the equivalent hand-built Expression.
+  #[test]
+  fn parsesArithmeticWithPrecedence() {
+    let parsed = parser::parse("2 + 3 * 4");
+    let (result, _) = evaluate(&parsed, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "INT:14");
+  }
+
+  #[test]
+  fn parsesParenthesesOverridePrecedence() {
+    let parsed = parser::parse("(2 + 3) * 4");
+    let (result, _) = evaluate(&parsed, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "INT:20");
+  }
+
+  #[test]
+  fn parsesLetAndVariableReads() {
+    let parsed = parser::parse("let x = 5 in x + 1");
+    let (result, _) = evaluate(&parsed, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "INT:6");
+  }
+
+  #[test]
+  fn parsesIfThenElse() {
+    let parsed = parser::parse("if 1 == 1 then 10 else 20");
+    let (result, _) = evaluate(&parsed, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "INT:10");
+  }
+
+  #[test]
+  fn parsesFunctionDeclarationAndCall() {
+    let parsed = parser::parse("let f = fn(a, b) -> a + b in f(3, 4)");
+    let (result, _) = evaluate(&parsed, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "INT:7");
+  }
+
+  // The bytecode VM should agree with the tree-walking evaluator on every
+  // construct it supports.
+  #[test]
+  fn vmAgreesWithEvaluateOnArithmeticAndCalls() {
+    let parsed = parser::parse("let f = fn(a, b) -> a + b * 2 in f(3, if 1 == 1 then 4 else 0)");
+    let (treeResult, _) = evaluate(&parsed, 0, &Environment::new()).unwrap();
+    let vmResult = vm::interpret(&parsed).unwrap();
+    assert_eq!(treeResult.to_string(), "INT:11");
+    assert_eq!(vmResult.to_string(), treeResult.to_string());
+  }
+
+  #[test]
+  fn vmReturnsRuntimeErrorInsteadOfPanickingOnUnsupportedOperators() {
+    let modExpr = Expression::binaryOperation(ArithmeticOperator::MOD, Expression::integerConstant(7), Expression::integerConstant(2));
+    assert!(vm::interpret(&modExpr).is_err());
+  }
+
+  #[test]
+  fn vmReturnsRuntimeErrorInsteadOfPanickingOnStringsAndArrays() {
+    let strExpr = Expression::stringConstant("hi".to_string());
+    assert!(vm::interpret(&strExpr).is_err());
+
+    let arrExpr = Expression::arrayLiteral(vec![Expression::integerConstant(1)]);
+    assert!(vm::interpret(&arrExpr).is_err());
+  }
+
+  // The static analyzer should catch unbound variables and arity mismatches
+  // without running the program.
+  #[test]
+  fn analyzeFindsUnboundVariable() {
+    let parsed = parser::parse("let x = 1 in x + typo");
+    let errors = analyze(&parsed);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "unbound variable 'typo'");
+  }
+
+  #[test]
+  fn analyzeFindsArityMismatch() {
+    let parsed = parser::parse("let f = fn(a, b) -> a + b in f(1)");
+    let errors = analyze(&parsed);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message, "'f' expects 2 argument(s), got 1");
+  }
+
+  #[test]
+  fn analyzeFindsNoErrorsOnWellFormedProgram() {
+    let parsed = parser::parse("let f = fn(a, b) -> a + b in f(3, 4)");
+    let errors = analyze(&parsed);
+    assert_eq!(errors.len(), 0);
+  }
+
+  // Optimizing an expression should never change what it evaluates to.
+  #[test]
+  fn optimizeFoldsNestedConstantsToTheSameResultAsEvaluate() {
+    let parsed = parser::parse("(400 + 74) / 3");
+    let (unoptimizedResult, _) = evaluate(&parsed, 0, &Environment::new()).unwrap();
+    let optimized = optimize(parsed);
+    assert!(matches!(optimized.tag, ExpressionTag::INT_CONST));
+    let (optimizedResult, _) = evaluate(&optimized, 0, &Environment::new()).unwrap();
+    assert_eq!(optimizedResult.to_string(), unoptimizedResult.to_string());
+  }
+
+  #[test]
+  fn optimizeEliminatesDeadBranch() {
+    let parsed = parser::parse("if 1 == 1 then 5 else 9");
+    let optimized = optimize(parsed);
+    assert!(matches!(optimized.tag, ExpressionTag::INT_CONST));
+    assert_eq!(optimized.intConst, 5);
+  }
+
+  // Regression test: a dead branch that would overflow if folded must be
+  // left unfolded instead of panicking, since `evaluate` never reaches it.
+  #[test]
+  fn optimizeDoesNotPanicOnOverflowInDeadBranch() {
+    let deadOverflow = Expression::binaryOperation(
+      ArithmeticOperator::TIMES,
+      Expression::integerConstant(100000),
+      Expression::integerConstant(100000)
+    );
+    let parsed = Expression::ifBranch(Expression::booleanConstant(true), Expression::integerConstant(0), deadOverflow);
+    let optimized = optimize(parsed);
+    let (result, _) = evaluate(&optimized, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "INT:0");
+  }
+
+  // String and array values: concatenation, indexing, equality, and bounds
+  // errors should all report through the same Value/RuntimeError machinery
+  // as every other expression.
+  #[test]
+  fn stringsConcatenateWithPlus() {
+    let exp = Expression::binaryOperation(ArithmeticOperator::PLUS, Expression::stringConstant("foo".to_string()), Expression::stringConstant("bar".to_string()));
+    let (result, _) = evaluate(&exp, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "STRING:\"foobar\"");
+  }
+
+  #[test]
+  fn arraysConcatenateWithPlus() {
+    let left = Expression::arrayLiteral(vec![Expression::integerConstant(1)]);
+    let right = Expression::arrayLiteral(vec![Expression::integerConstant(2)]);
+    let exp = Expression::binaryOperation(ArithmeticOperator::PLUS, left, right);
+    let (result, _) = evaluate(&exp, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "ARRAY:[INT:1, INT:2]");
+  }
+
+  #[test]
+  fn arrayIndexingReadsTheElementAtThatPosition() {
+    let arr = Expression::arrayLiteral(vec![Expression::integerConstant(10), Expression::integerConstant(20)]);
+    let exp = Expression::index(arr, Expression::integerConstant(1));
+    let (result, _) = evaluate(&exp, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "INT:20");
+  }
+
+  #[test]
+  fn outOfBoundsIndexingReportsIndexOutOfBounds() {
+    let arr = Expression::arrayLiteral(vec![Expression::integerConstant(10), Expression::integerConstant(20)]);
+    let exp = Expression::index(arr, Expression::integerConstant(5));
+    match evaluate(&exp, 0, &Environment::new()) {
+      Err(err) => assert!(matches!(err.kind, RuntimeErrorKind::IndexOutOfBounds)),
+      Ok(_) => panic!("expected an IndexOutOfBounds error"),
+    }
+  }
+
+  #[test]
+  fn equalStringsAndArraysCompareEqualByValue() {
+    let left = Expression::stringConstant("abc".to_string());
+    let right = Expression::stringConstant("abc".to_string());
+    let exp = Expression::comparison(ComparisonOperator::EQ, left, right);
+    let (result, _) = evaluate(&exp, 0, &Environment::new()).unwrap();
+    assert_eq!(result.to_string(), "BOOL:true");
+
+    let leftArr = Expression::arrayLiteral(vec![Expression::integerConstant(1), Expression::integerConstant(2)]);
+    let rightArr = Expression::arrayLiteral(vec![Expression::integerConstant(1), Expression::integerConstant(2)]);
+    let arrExp = Expression::comparison(ComparisonOperator::EQ, leftArr, rightArr);
+    let (arrResult, _) = evaluate(&arrExp, 0, &Environment::new()).unwrap();
+    assert_eq!(arrResult.to_string(), "BOOL:true");
+  }
 }
\ No newline at end of file